@@ -5,59 +5,51 @@
 
 use std::collections::HashMap;
 
-use crate::models::{Fill, Order, OrderAction, OrderStatus, Side, Trade};
+use crate::fixed::Fixed;
+use crate::models::{
+    Fill, Order, OrderAction, OrderStatus, OutcomePartition, PartitionError, Side, TimeInForce, Trade,
+};
+use crate::portfolio::Portfolio;
+
+/// Selector for `Broker::cancel_all`.
+pub enum CancelScope<'a> {
+    /// Cancel every pending order, across all markets.
+    All,
+    /// Cancel every pending order in a single market.
+    Market(&'a str),
+    /// Cancel every pending order tagged with this group id, across all
+    /// markets (see `Broker::place_market_making_quotes`).
+    Group(&'a str),
+}
 
 pub struct Broker {
     /// Orders indexed by market_id for fast lookup.
     pending: HashMap<String, Vec<Order>>,
-    commission_rate: f64,
-    slippage: f64,
+    commission_rate: Fixed,
+    slippage: Fixed,
     liquidity_cap: bool,
     next_id: u64,
     /// Exponential moving average of trade size per market.
     /// Used by the square-root market impact model to scale slippage.
-    ema_trade_size: HashMap<String, f64>,
-    ema_decay: f64,
+    ema_trade_size: HashMap<String, Fixed>,
+    ema_decay: Fixed,
 }
 
 /// Check if an order should fill against a trade. Returns fill price if matched.
 ///
-/// Taker-side-aware: a resting limit order only fills when the trade taker is on
-/// the opposite side. This correctly models CLOB maker/taker semantics.
-///   YES bid fills on NO taker  (someone selling YES hits our bid)
-///   YES ask fills on YES taker (someone buying YES hits our ask)
-///   NO  bid fills on YES taker (someone selling NO  hits our bid)
-///   NO  ask fills on NO taker  (someone buying NO  hits our ask)
-fn match_order(order: &Order, trade: &Trade) -> Option<f64> {
-    match (order.action, order.side) {
-        (OrderAction::Buy, Side::Yes) => {
-            if trade.taker_side == Side::No && trade.yes_price <= order.price {
-                Some(trade.yes_price)
-            } else {
-                None
-            }
-        }
-        (OrderAction::Sell, Side::Yes) => {
-            if trade.taker_side == Side::Yes && trade.yes_price >= order.price {
-                Some(trade.yes_price)
-            } else {
-                None
-            }
-        }
-        (OrderAction::Buy, Side::No) => {
-            if trade.taker_side == Side::Yes && trade.no_price <= order.price {
-                Some(trade.no_price)
-            } else {
-                None
-            }
-        }
-        (OrderAction::Sell, Side::No) => {
-            if trade.taker_side == Side::No && trade.no_price >= order.price {
-                Some(trade.no_price)
-            } else {
-                None
-            }
-        }
+/// Each outcome has its own resting book, so a trade only concerns orders on
+/// the outcome it actually traded (`trade.taker_side`) — never the other
+/// outcomes in the same market:
+///   a bid on outcome X fills when X trades at or below the bid price
+///   an ask on outcome X fills when X trades at or above the ask price
+fn match_order(order: &Order, trade: &Trade) -> Option<Fixed> {
+    if trade.taker_side != order.side {
+        return None;
+    }
+    let trade_price = *trade.prices.get(order.side.index())?;
+    match order.action {
+        OrderAction::Buy => (trade_price <= order.price).then_some(trade_price),
+        OrderAction::Sell => (trade_price >= order.price).then_some(trade_price),
     }
 }
 
@@ -72,34 +64,45 @@ fn match_order(order: &Order, trade: &Trade) -> Option<f64> {
 ///      trade size pay more (standard Almgren-Chriss / Kyle-lambda approach).
 ///
 /// Both effects are at least 1× so the minimum cost is always base_slippage.
-/// Result is clamped to [0.01, 0.99].
+/// Result is clamped to [0.01, 0.99]. The division and square root are both
+/// checked, falling back to the same floor the inputs are already clamped
+/// against on a degenerate input.
 fn apply_market_impact(
-    base_slippage: f64,
-    price: f64,
+    base_slippage: Fixed,
+    price: Fixed,
     action: OrderAction,
-    order_qty: f64,
-    avg_trade_size: f64,
-) -> f64 {
-    if base_slippage == 0.0 {
+    order_qty: Fixed,
+    avg_trade_size: Fixed,
+) -> Fixed {
+    if base_slippage.is_zero() {
         return price;
     }
+    let one = Fixed::ONE;
+    let variance_floor = Fixed::from_f64(0.01);
+    let max_spread_factor = Fixed::from_f64(25.0);
+
     // Spread factor: 1 / (4 * p * (1-p)), floored so the max multiplier is ~25×.
-    let variance = (price * (1.0 - price)).max(0.01);
-    let spread_factor = (0.25 / variance).max(1.0);
+    let variance = (price * (one - price)).max(variance_floor);
+    let spread_factor = Fixed::from_f64(0.25)
+        .checked_div(variance)
+        .unwrap_or(max_spread_factor)
+        .max(one);
 
     // Size factor: sqrt(order / avg_trade), at least 1×.
-    let size_ratio = order_qty / avg_trade_size.max(0.01);
-    let size_factor = size_ratio.sqrt().max(1.0);
+    let size_ratio = order_qty
+        .checked_div(avg_trade_size.max(variance_floor))
+        .unwrap_or(Fixed::ZERO);
+    let size_factor = size_ratio.checked_sqrt().unwrap_or(Fixed::ZERO).max(one);
 
     let impact = base_slippage * spread_factor * size_factor;
     match action {
-        OrderAction::Buy => (price + impact).min(0.99),
-        OrderAction::Sell => (price - impact).max(0.01),
+        OrderAction::Buy => (price + impact).min(Fixed::from_f64(0.99)),
+        OrderAction::Sell => (price - impact).max(Fixed::from_f64(0.01)),
     }
 }
 
 impl Broker {
-    pub fn new(commission_rate: f64, slippage: f64, liquidity_cap: bool, ema_decay: f64) -> Self {
+    pub fn new(commission_rate: Fixed, slippage: Fixed, liquidity_cap: bool, ema_decay: Fixed) -> Self {
         Self {
             pending: HashMap::new(),
             commission_rate,
@@ -112,19 +115,22 @@ impl Broker {
     }
 
     /// Update the EMA of trade size for a market. Call on every trade before check_fills.
-    pub fn update_trade_size(&mut self, market_id: &str, trade_qty: f64) {
+    pub fn update_trade_size(&mut self, market_id: &str, trade_qty: Fixed) {
         let entry = self.ema_trade_size.entry(market_id.to_string()).or_insert(trade_qty);
-        *entry = *entry * (1.0 - self.ema_decay) + trade_qty * self.ema_decay;
+        *entry = *entry * (Fixed::ONE - self.ema_decay) + trade_qty * self.ema_decay;
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn place_order(
         &mut self,
         market_id: &str,
         action: OrderAction,
         side: Side,
-        price: f64,
-        quantity: f64,
+        price: Fixed,
+        quantity: Fixed,
         timestamp: f64,
+        time_in_force: TimeInForce,
+        group_id: Option<String>,
     ) -> Order {
         let order = Order {
             order_id: self.next_id.to_string(),
@@ -135,9 +141,11 @@ impl Broker {
             quantity,
             status: OrderStatus::Pending,
             created_at: timestamp,
+            time_in_force,
             filled_at: None,
             fill_price: None,
-            filled_quantity: 0.0,
+            filled_quantity: Fixed::ZERO,
+            group_id,
         };
         self.next_id += 1;
         self.pending
@@ -147,6 +155,130 @@ impl Broker {
         order
     }
 
+    /// Place a grouped ladder of market-making quotes around `mid_price`.
+    ///
+    /// For each of `levels` (1-indexed step multiples of `spread`), places a
+    /// bid below and an ask above the center price, on both `Side::YES`
+    /// (centered on `mid_price`) and `Side::NO` (centered on its complement
+    /// `1 - mid_price`, since YES + NO ≈ 1), each sized at `level_size`.
+    /// Every resulting `Order` is tagged with `group_id` so the whole
+    /// ladder can be pulled in one call via
+    /// `cancel_all(CancelScope::Group(group_id))`. Prices are clamped to
+    /// `[0.01, 0.99]`. Each leg goes through
+    /// `portfolio.place_order_if_healthy` independently, so a ladder can
+    /// come back shorter than `levels * 4` orders if some legs would have
+    /// pushed account health negative.
+    #[allow(clippy::too_many_arguments)]
+    pub fn place_market_making_quotes(
+        &mut self,
+        portfolio: &Portfolio,
+        market_id: &str,
+        mid_price: Fixed,
+        spread: Fixed,
+        levels: u32,
+        level_size: Fixed,
+        group_id: &str,
+        timestamp: f64,
+    ) -> Vec<Order> {
+        let price_floor = Fixed::from_f64(0.01);
+        let price_ceil = Fixed::from_f64(0.99);
+
+        let mut orders = Vec::with_capacity(levels as usize * 4);
+        for level in 1..=levels {
+            let step = spread * Fixed::from_f64(level as f64);
+
+            for (side, center) in [(Side::YES, mid_price), (Side::NO, Fixed::ONE - mid_price)] {
+                let bid_price = (center - step).clamp(price_floor, price_ceil);
+                let ask_price = (center + step).clamp(price_floor, price_ceil);
+
+                orders.extend(portfolio.place_order_if_healthy(
+                    self,
+                    market_id,
+                    OrderAction::Buy,
+                    side,
+                    bid_price,
+                    level_size,
+                    timestamp,
+                    TimeInForce::Gtc,
+                    Some(group_id.to_string()),
+                ));
+                orders.extend(portfolio.place_order_if_healthy(
+                    self,
+                    market_id,
+                    OrderAction::Sell,
+                    side,
+                    ask_price,
+                    level_size,
+                    timestamp,
+                    TimeInForce::Gtc,
+                    Some(group_id.to_string()),
+                ));
+            }
+        }
+        orders
+    }
+
+    /// Place a combinatorial bet as one order per non-keep outcome in `partition`.
+    ///
+    /// `total_quantity` is split evenly across the buy group and, separately,
+    /// across the sell group, so going long the buy group and short the sell
+    /// group stays self-financing under the $1-per-complete-set invariant.
+    /// `prices` gives the current price of every outcome in the market,
+    /// indexed the same way as `partition` — missing an entry for a
+    /// traded outcome is rejected as `PartitionError::MissingPrice` rather
+    /// than defaulting that leg to `Fixed::ZERO`. Each leg goes through
+    /// `portfolio.place_order_if_healthy` independently, same as
+    /// `place_market_making_quotes`, so the returned `Vec` can be shorter
+    /// than `buy.len() + sell.len()` if a leg would have breached health.
+    pub fn place_combinatorial_order(
+        &mut self,
+        portfolio: &Portfolio,
+        market_id: &str,
+        partition: &OutcomePartition,
+        prices: &[Fixed],
+        total_quantity: Fixed,
+        timestamp: f64,
+    ) -> Result<Vec<Order>, PartitionError> {
+        partition.validate()?;
+        if partition.buy.iter().chain(partition.sell.iter()).any(|&idx| prices.get(idx).is_none()) {
+            return Err(PartitionError::MissingPrice);
+        }
+
+        let buy_qty = total_quantity / Fixed::from_f64(partition.buy.len() as f64);
+        let sell_qty = total_quantity / Fixed::from_f64(partition.sell.len() as f64);
+
+        let mut orders = Vec::with_capacity(partition.buy.len() + partition.sell.len());
+        for &idx in &partition.buy {
+            let price = prices[idx];
+            orders.extend(portfolio.place_order_if_healthy(
+                self,
+                market_id,
+                OrderAction::Buy,
+                Side(idx),
+                price,
+                buy_qty,
+                timestamp,
+                TimeInForce::Gtc,
+                None,
+            ));
+        }
+        for &idx in &partition.sell {
+            let price = prices[idx];
+            orders.extend(portfolio.place_order_if_healthy(
+                self,
+                market_id,
+                OrderAction::Sell,
+                Side(idx),
+                price,
+                sell_qty,
+                timestamp,
+                TimeInForce::Gtc,
+                None,
+            ));
+        }
+        Ok(orders)
+    }
+
     pub fn cancel_order(&mut self, order_id: &str) -> bool {
         for orders in self.pending.values_mut() {
             if let Some(pos) = orders.iter().position(|o| o.order_id == order_id) {
@@ -157,25 +289,43 @@ impl Broker {
         false
     }
 
-    pub fn cancel_all(&mut self, market_id: Option<&str>) -> usize {
-        match market_id {
-            Some(mid) => {
+    /// Cancel pending orders matching `scope`. Returns the number cancelled.
+    pub fn cancel_all(&mut self, scope: CancelScope) -> usize {
+        match scope {
+            CancelScope::All => {
+                let count: usize = self.pending.values().map(|v| v.len()).sum();
+                self.pending.clear();
+                count
+            }
+            CancelScope::Market(mid) => {
                 if let Some(orders) = self.pending.remove(mid) {
                     orders.len()
                 } else {
                     0
                 }
             }
-            None => {
-                let count: usize = self.pending.values().map(|v| v.len()).sum();
-                self.pending.clear();
+            CancelScope::Group(group_id) => {
+                let mut count = 0;
+                for orders in self.pending.values_mut() {
+                    let before = orders.len();
+                    orders.retain(|o| o.group_id.as_deref() != Some(group_id));
+                    count += before - orders.len();
+                }
                 count
             }
         }
     }
 
     /// Check all pending orders for this trade's market. O(orders_for_market).
-    pub fn check_fills(&mut self, trade: &Trade, available_cash: f64) -> Vec<Fill> {
+    ///
+    /// A matched order fills incrementally: only its remaining quantity
+    /// (`quantity - filled_quantity`) is eligible, `fill_price` becomes the
+    /// volume-weighted average across every fill it has received, and it
+    /// only transitions to `Filled` (and is removed) once fully consumed —
+    /// otherwise it stays pending as `PartiallyFilled`. GTT orders whose
+    /// expiry has passed are cancelled before matching and returned
+    /// alongside the fills so callers can react to the expiry.
+    pub fn check_fills(&mut self, trade: &Trade, available_cash: Fixed) -> (Vec<Fill>, Vec<Order>) {
         let commission_rate = self.commission_rate;
         let slippage = self.slippage;
         let liquidity_cap = self.liquidity_cap;
@@ -187,33 +337,45 @@ impl Broker {
 
         let orders = match self.pending.get_mut(&trade.market_id) {
             Some(orders) if !orders.is_empty() => orders,
-            _ => return vec![],
+            _ => return (vec![], vec![]),
         };
 
         let mut fills: Vec<Fill> = Vec::new();
+        let mut expired: Vec<Order> = Vec::new();
         let mut cash = available_cash;
         let mut remaining_liq = if liquidity_cap {
             trade.quantity
         } else {
-            f64::INFINITY
+            Fixed::from_f64(f64::INFINITY)
         };
         let mut to_remove: Vec<usize> = Vec::new();
+        let min_fillable = Fixed::ONE;
 
         for (idx, order) in orders.iter_mut().enumerate() {
+            if let TimeInForce::Gtt(expiry) = order.time_in_force {
+                if trade.timestamp >= expiry {
+                    order.status = OrderStatus::Cancelled;
+                    expired.push(order.clone());
+                    to_remove.push(idx);
+                    continue;
+                }
+            }
+
             let fill_price = match match_order(order, trade) {
                 Some(p) => p,
                 None => continue,
             };
 
+            let remaining_qty = order.quantity - order.filled_quantity;
             let fill_price =
-                apply_market_impact(slippage, fill_price, order.action, order.quantity, avg_trade_size);
+                apply_market_impact(slippage, fill_price, order.action, remaining_qty, avg_trade_size);
 
             let mut fill_qty = if liquidity_cap {
-                order.quantity.min(remaining_liq)
+                remaining_qty.min(remaining_liq)
             } else {
-                order.quantity
+                remaining_qty
             };
-            if fill_qty <= 0.0 {
+            if fill_qty <= Fixed::ZERO {
                 continue;
             }
 
@@ -222,9 +384,11 @@ impl Broker {
 
             if order.action == OrderAction::Buy && cost + commission > cash {
                 if liquidity_cap {
-                    let max_qty = cash / (fill_price * (1.0 + commission_rate));
+                    let max_qty = cash
+                        .checked_div(fill_price * (Fixed::ONE + commission_rate))
+                        .unwrap_or(Fixed::ZERO);
                     fill_qty = fill_qty.min(max_qty);
-                    if fill_qty < 1.0 {
+                    if fill_qty < min_fillable {
                         continue;
                     }
                     fill_qty = fill_qty.floor();
@@ -251,19 +415,32 @@ impl Broker {
                 commission,
             });
 
-            order.status = OrderStatus::Filled;
+            let new_filled_quantity = order.filled_quantity + fill_qty;
+            order.fill_price = Some(match order.fill_price {
+                Some(prev_price) => {
+                    (prev_price * order.filled_quantity + fill_price * fill_qty) / new_filled_quantity
+                }
+                None => fill_price,
+            });
+            order.filled_quantity = new_filled_quantity;
             order.filled_at = Some(trade.timestamp);
-            order.fill_price = Some(fill_price);
-            order.filled_quantity = fill_qty;
-            to_remove.push(idx);
+
+            if (order.quantity - order.filled_quantity).is_zero() {
+                order.status = OrderStatus::Filled;
+                to_remove.push(idx);
+            } else {
+                order.status = OrderStatus::PartiallyFilled;
+            }
         }
 
-        // Remove filled orders (reverse order to preserve indices)
+        // Remove filled/expired orders (reverse order to preserve indices)
+        to_remove.sort_unstable();
+        to_remove.dedup();
         for &idx in to_remove.iter().rev() {
             orders.remove(idx);
         }
 
-        fills
+        (fills, expired)
     }
 
     /// Return references to all pending orders (flattened).
@@ -271,3 +448,187 @@ impl Broker {
         self.pending.values().flat_map(|v| v.iter()).collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(market_id: &str, prices: Vec<f64>, quantity: f64, taker_side: Side) -> Trade {
+        Trade {
+            timestamp: 0.0,
+            market_id: market_id.to_string(),
+            prices: prices.into_iter().map(Fixed::from_f64).collect(),
+            quantity: Fixed::from_f64(quantity),
+            taker_side,
+        }
+    }
+
+    #[test]
+    fn trade_on_one_outcome_does_not_fill_resting_orders_on_other_outcomes() {
+        let mut broker = Broker::new(Fixed::ZERO, Fixed::ZERO, false, Fixed::from_f64(0.1));
+        // A 3-outcome categorical market: one resting bid per outcome.
+        for (side, price) in [(Side(0), 0.40), (Side(1), 0.30), (Side(2), 0.30)] {
+            broker.place_order(
+                "m",
+                OrderAction::Buy,
+                side,
+                Fixed::from_f64(price),
+                Fixed::from_f64(10.0),
+                0.0,
+                TimeInForce::Gtc,
+                None,
+            );
+        }
+
+        // A trade on outcome 0 alone must not fill the resting bids on 1 or 2.
+        let trade = trade("m", vec![0.40, 0.30, 0.30], 10.0, Side(0));
+        let (fills, _) = broker.check_fills(&trade, Fixed::from_f64(1000.0));
+
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].side, Side(0));
+        assert_eq!(broker.all_pending().len(), 2);
+    }
+
+    #[test]
+    fn bid_and_ask_on_same_outcome_match_by_price() {
+        let mut broker = Broker::new(Fixed::ZERO, Fixed::ZERO, false, Fixed::from_f64(0.1));
+        broker.place_order(
+            "m",
+            OrderAction::Buy,
+            Side::YES,
+            Fixed::from_f64(0.50),
+            Fixed::from_f64(10.0),
+            0.0,
+            TimeInForce::Gtc,
+            None,
+        );
+        broker.place_order(
+            "m",
+            OrderAction::Sell,
+            Side::YES,
+            Fixed::from_f64(0.50),
+            Fixed::from_f64(10.0),
+            0.0,
+            TimeInForce::Gtc,
+            None,
+        );
+
+        let trade = trade("m", vec![0.50, 0.50], 10.0, Side::YES);
+        let (fills, _) = broker.check_fills(&trade, Fixed::from_f64(1000.0));
+
+        assert_eq!(fills.len(), 2);
+        assert!(broker.all_pending().is_empty());
+    }
+
+    #[test]
+    fn partial_fills_accumulate_across_multiple_trades() {
+        let mut broker = Broker::new(Fixed::ZERO, Fixed::ZERO, true, Fixed::from_f64(0.1));
+        broker.place_order(
+            "m",
+            OrderAction::Buy,
+            Side::YES,
+            Fixed::from_f64(0.60),
+            Fixed::from_f64(10.0),
+            0.0,
+            TimeInForce::Gtc,
+            None,
+        );
+
+        let first = trade("m", vec![0.50, 0.50], 4.0, Side::YES);
+        let (fills, _) = broker.check_fills(&first, Fixed::from_f64(1000.0));
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].quantity, 4.0);
+
+        let pending = broker.all_pending();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].status, OrderStatus::PartiallyFilled);
+        assert_eq!(pending[0].filled_quantity, 4.0);
+
+        let second = trade("m", vec![0.55, 0.45], 6.0, Side::YES);
+        let (fills, _) = broker.check_fills(&second, Fixed::from_f64(1000.0));
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].quantity, 6.0);
+        assert!(broker.all_pending().is_empty());
+    }
+
+    #[test]
+    fn gtt_order_is_cancelled_and_returned_once_expired() {
+        let mut broker = Broker::new(Fixed::ZERO, Fixed::ZERO, false, Fixed::from_f64(0.1));
+        broker.place_order(
+            "m",
+            OrderAction::Buy,
+            Side::YES,
+            Fixed::from_f64(0.60),
+            Fixed::from_f64(10.0),
+            0.0,
+            TimeInForce::Gtt(100.0),
+            None,
+        );
+
+        let late_trade = Trade {
+            timestamp: 150.0,
+            market_id: "m".to_string(),
+            prices: vec![Fixed::from_f64(0.50), Fixed::from_f64(0.50)],
+            quantity: Fixed::from_f64(10.0),
+            taker_side: Side::YES,
+        };
+        let (fills, expired) = broker.check_fills(&late_trade, Fixed::from_f64(1000.0));
+
+        assert!(fills.is_empty());
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].status, OrderStatus::Cancelled);
+        assert!(broker.all_pending().is_empty());
+    }
+
+    #[test]
+    fn market_making_ladder_quotes_no_around_its_own_complement_price() {
+        let portfolio = Portfolio::new(Fixed::from_f64(1_000_000.0), Fixed::ZERO, Fixed::ZERO, 0);
+        let mut broker = Broker::new(Fixed::ZERO, Fixed::ZERO, false, Fixed::from_f64(0.1));
+
+        // mid_price = 0.70 is nowhere near 50/50, so the NO leg must be
+        // centered on 1 - 0.70 = 0.30, not reuse the YES-centered levels.
+        let orders = broker.place_market_making_quotes(
+            &portfolio,
+            "m",
+            Fixed::from_f64(0.70),
+            Fixed::from_f64(0.02),
+            1,
+            Fixed::from_f64(10.0),
+            "ladder-1",
+            0.0,
+        );
+
+        let yes_bid = orders
+            .iter()
+            .find(|o| o.side == Side::YES && o.action == OrderAction::Buy)
+            .unwrap();
+        let no_bid = orders
+            .iter()
+            .find(|o| o.side == Side::NO && o.action == OrderAction::Buy)
+            .unwrap();
+        let no_ask = orders
+            .iter()
+            .find(|o| o.side == Side::NO && o.action == OrderAction::Sell)
+            .unwrap();
+
+        assert_eq!(yes_bid.price, 0.68);
+        assert_eq!(no_bid.price, 0.28);
+        assert_eq!(no_ask.price, 0.32);
+    }
+
+    #[test]
+    fn combinatorial_order_rejects_a_partition_missing_a_price_instead_of_defaulting_to_zero() {
+        let portfolio = Portfolio::new(Fixed::from_f64(1_000_000.0), Fixed::ZERO, Fixed::ZERO, 0);
+        let mut broker = Broker::new(Fixed::ZERO, Fixed::ZERO, false, Fixed::from_f64(0.1));
+        let partition = OutcomePartition::new(vec![0], vec![1], vec![2], 3);
+
+        // Only outcome 0 of the partition's 3 outcomes has a price, so the
+        // sell leg on outcome 1 is unpriceable.
+        let prices = [Fixed::from_f64(0.40)];
+        let result =
+            broker.place_combinatorial_order(&portfolio, "m", &partition, &prices, Fixed::from_f64(10.0), 0.0);
+
+        assert!(matches!(result, Err(PartitionError::MissingPrice)));
+        assert!(broker.all_pending().is_empty());
+    }
+}
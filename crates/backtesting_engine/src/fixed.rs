@@ -0,0 +1,293 @@
+/// 128-bit fixed-point decimal for deterministic, bit-reproducible accounting.
+///
+/// Every price, quantity, cash, and PnL figure in models/broker/portfolio is
+/// a `Fixed` rather than an `f64`, so arithmetic never drifts across
+/// platforms. Every `+`/`-`/`*`/`/` is backed by the `checked_*` methods
+/// below; on overflow (or division by zero) the operator saturates to
+/// `Fixed::MAX`/`Fixed::MIN` rather than wrapping, producing NaN, or
+/// panicking mid-backtest. Call `checked_add`/`checked_sub`/`checked_mul`/
+/// `checked_div` directly where a saturated result needs to be detected and
+/// handled explicitly instead. Conversion to/from Python floats happens
+/// only at the PyO3 FFI boundary in engine.rs — everything internal stays
+/// fixed-point.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+/// Number of decimal digits of precision below the point.
+pub const SCALE: u32 = 9;
+const SCALE_FACTOR: i128 = 1_000_000_000; // 10^SCALE
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Fixed(i128);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FixedError {
+    Overflow,
+    DivisionByZero,
+}
+
+impl Fixed {
+    pub const ZERO: Fixed = Fixed(0);
+    pub const ONE: Fixed = Fixed(SCALE_FACTOR);
+    /// Saturation ceiling/floor for the operator overloads below.
+    pub const MAX: Fixed = Fixed(i128::MAX);
+    pub const MIN: Fixed = Fixed(i128::MIN);
+
+    pub fn from_f64(value: f64) -> Self {
+        Fixed((value * SCALE_FACTOR as f64).round() as i128)
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / SCALE_FACTOR as f64
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Result<Self, FixedError> {
+        self.0.checked_add(rhs.0).map(Fixed).ok_or(FixedError::Overflow)
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Result<Self, FixedError> {
+        self.0.checked_sub(rhs.0).map(Fixed).ok_or(FixedError::Overflow)
+    }
+
+    pub fn checked_mul(self, rhs: Self) -> Result<Self, FixedError> {
+        self.0
+            .checked_mul(rhs.0)
+            .and_then(|p| p.checked_div(SCALE_FACTOR))
+            .map(Fixed)
+            .ok_or(FixedError::Overflow)
+    }
+
+    pub fn checked_div(self, rhs: Self) -> Result<Self, FixedError> {
+        if rhs.0 == 0 {
+            return Err(FixedError::DivisionByZero);
+        }
+        self.0
+            .checked_mul(SCALE_FACTOR)
+            .and_then(|n| n.checked_div(rhs.0))
+            .map(Fixed)
+            .ok_or(FixedError::Overflow)
+    }
+
+    /// Checked square root, guarded against the degenerate/negative inputs
+    /// that a plain `f64::sqrt` would silently turn into NaN.
+    pub fn checked_sqrt(self) -> Result<Self, FixedError> {
+        if self.0 < 0 {
+            return Err(FixedError::Overflow);
+        }
+        if self.0 == 0 {
+            return Ok(Fixed::ZERO);
+        }
+        // sqrt(mantissa / SCALE) expressed in scaled terms is
+        // sqrt(mantissa * SCALE), since sqrt(x/s) * s == sqrt(x * s).
+        let radicand = self.0.checked_mul(SCALE_FACTOR).ok_or(FixedError::Overflow)?;
+        Ok(Fixed(isqrt(radicand)))
+    }
+
+    /// Saturates to `Fixed::MAX` instead of overflowing when called on
+    /// `Fixed::MIN`, the one magnitude with no positive counterpart.
+    pub fn abs(self) -> Self {
+        if self.0 == i128::MIN {
+            Fixed::MAX
+        } else {
+            Fixed(self.0.abs())
+        }
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+
+    pub fn min(self, other: Self) -> Self {
+        if self.0 <= other.0 {
+            self
+        } else {
+            other
+        }
+    }
+
+    pub fn max(self, other: Self) -> Self {
+        if self.0 >= other.0 {
+            self
+        } else {
+            other
+        }
+    }
+
+    pub fn clamp(self, lo: Self, hi: Self) -> Self {
+        self.max(lo).min(hi)
+    }
+
+    /// Round toward negative infinity to a whole number, e.g. for flooring
+    /// a fractional contract quantity down to a tradable whole amount.
+    pub fn floor(self) -> Self {
+        Fixed(self.0 - self.0.rem_euclid(SCALE_FACTOR))
+    }
+}
+
+/// Integer square root via Newton's method (Heron's method), exact for
+/// perfect squares and floor-rounded otherwise.
+fn isqrt(n: i128) -> i128 {
+    if n < 2 {
+        return n;
+    }
+    let mut x = n;
+    let mut y = (x + 1) / 2;
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
+}
+
+impl Add for Fixed {
+    type Output = Fixed;
+    /// Saturates to `Fixed::MAX`/`MIN` on overflow instead of panicking, so
+    /// a backtest never crashes on a single corrupted figure — but the
+    /// saturation is never silent: it's logged to stderr (see `log_clamp`)
+    /// so a run that hit it doesn't look indistinguishable from a clean one.
+    fn add(self, rhs: Self) -> Self {
+        let fallback = if rhs.0 >= 0 { Fixed::MAX } else { Fixed::MIN };
+        self.checked_add(rhs)
+            .unwrap_or_else(|_| log_clamp("addition", self, rhs, fallback))
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Fixed;
+    /// Saturates to `Fixed::MAX`/`MIN` on overflow instead of panicking; see `Add`.
+    fn sub(self, rhs: Self) -> Self {
+        let fallback = if rhs.0 <= 0 { Fixed::MAX } else { Fixed::MIN };
+        self.checked_sub(rhs)
+            .unwrap_or_else(|_| log_clamp("subtraction", self, rhs, fallback))
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Fixed;
+    /// Saturates to `Fixed::MAX`/`MIN` on overflow instead of panicking; see `Add`.
+    fn mul(self, rhs: Self) -> Self {
+        let negative_result = (self.0 < 0) != (rhs.0 < 0);
+        let fallback = if negative_result { Fixed::MIN } else { Fixed::MAX };
+        self.checked_mul(rhs)
+            .unwrap_or_else(|_| log_clamp("multiplication", self, rhs, fallback))
+    }
+}
+
+impl Div for Fixed {
+    type Output = Fixed;
+    /// Saturates to `Fixed::MAX`/`MIN` on overflow or division by zero
+    /// instead of panicking; see `Add`.
+    fn div(self, rhs: Self) -> Self {
+        let negative_result = (self.0 < 0) != (rhs.0 < 0);
+        let fallback = if negative_result { Fixed::MIN } else { Fixed::MAX };
+        self.checked_div(rhs)
+            .unwrap_or_else(|_| log_clamp("division", self, rhs, fallback))
+    }
+}
+
+/// Report a saturating clamp to stderr so it's never silently
+/// indistinguishable from a clean run, then return the clamped value.
+fn log_clamp(op: &str, lhs: Fixed, rhs: Fixed, fallback: Fixed) -> Fixed {
+    eprintln!("Fixed {op} overflowed ({lhs:?}, {rhs:?}); saturating to {fallback:?}");
+    fallback
+}
+
+impl AddAssign for Fixed {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Fixed {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Fixed;
+    /// Saturates to `Fixed::MAX` instead of overflowing when negating
+    /// `Fixed::MIN`, the one magnitude with no positive counterpart.
+    fn neg(self) -> Self {
+        if self.0 == i128::MIN {
+            Fixed::MAX
+        } else {
+            Fixed(-self.0)
+        }
+    }
+}
+
+impl std::iter::Sum for Fixed {
+    fn sum<I: Iterator<Item = Fixed>>(iter: I) -> Self {
+        iter.fold(Fixed::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl fmt::Display for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_f64())
+    }
+}
+
+impl PartialEq<f64> for Fixed {
+    fn eq(&self, other: &f64) -> bool {
+        *self == Fixed::from_f64(*other)
+    }
+}
+
+impl PartialOrd<f64> for Fixed {
+    fn partial_cmp(&self, other: &f64) -> Option<Ordering> {
+        self.partial_cmp(&Fixed::from_f64(*other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_sub_mul_div_round_trip_through_f64() {
+        let a = Fixed::from_f64(0.30);
+        let b = Fixed::from_f64(0.10);
+        assert_eq!(a + b, 0.40);
+        assert_eq!(a - b, 0.20);
+        assert_eq!(a * Fixed::from_f64(10.0), 3.0);
+        assert_eq!(a / Fixed::from_f64(3.0), 0.10);
+    }
+
+    #[test]
+    fn checked_sqrt_matches_known_values() {
+        assert_eq!(Fixed::from_f64(4.0).checked_sqrt().unwrap(), 2.0);
+        assert_eq!(Fixed::ZERO.checked_sqrt().unwrap(), Fixed::ZERO);
+        assert_eq!(Fixed::from_f64(-1.0).checked_sqrt(), Err(FixedError::Overflow));
+    }
+
+    #[test]
+    fn checked_div_rejects_division_by_zero() {
+        assert_eq!(Fixed::ONE.checked_div(Fixed::ZERO), Err(FixedError::DivisionByZero));
+    }
+
+    #[test]
+    fn add_saturates_instead_of_panicking_on_overflow() {
+        assert_eq!(Fixed::MAX + Fixed::ONE, Fixed::MAX);
+        assert_eq!(Fixed::MIN - Fixed::ONE, Fixed::MIN);
+    }
+
+    #[test]
+    fn div_saturates_instead_of_panicking_on_division_by_zero() {
+        assert_eq!(Fixed::ONE / Fixed::ZERO, Fixed::MAX);
+        assert_eq!(-Fixed::ONE / Fixed::ZERO, Fixed::MIN);
+    }
+
+    #[test]
+    fn abs_and_neg_saturate_on_the_unrepresentable_min_magnitude() {
+        assert_eq!(Fixed::MIN.abs(), Fixed::MAX);
+        assert_eq!(-Fixed::MIN, Fixed::MAX);
+    }
+}
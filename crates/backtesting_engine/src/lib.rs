@@ -2,8 +2,10 @@ use pyo3::prelude::*;
 
 mod broker;
 mod engine;
+mod fixed;
 mod models;
 mod portfolio;
+mod rebalance;
 
 #[pymodule]
 fn backtesting_engine(m: &Bound<'_, PyModule>) -> PyResult<()> {
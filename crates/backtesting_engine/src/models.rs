@@ -1,19 +1,33 @@
 /// Core data types for the backtesting engine.
 ///
-/// Internal Rust representations â€” not exposed to Python directly.
+/// Internal Rust representations — not exposed to Python directly.
 /// Conversion to/from Python objects happens in engine.rs at FFI boundaries.
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum Side {
-    Yes,
-    No,
-}
+use std::collections::HashMap;
+
+use crate::fixed::Fixed;
+
+/// Index of an outcome within a market's mutually exclusive outcome set.
+///
+/// Binary YES/NO markets are just the two-outcome case: `Side::YES` is
+/// outcome 0 and `Side::NO` is outcome 1. Categorical markets with N ≥ 2
+/// outcomes use indices 0..N the same way.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Side(pub usize);
 
 impl Side {
-    pub fn as_str(&self) -> &'static str {
-        match self {
-            Side::Yes => "yes",
-            Side::No => "no",
+    pub const YES: Side = Side(0);
+    pub const NO: Side = Side(1);
+
+    pub fn index(&self) -> usize {
+        self.0
+    }
+
+    pub fn as_str(&self) -> String {
+        match self.0 {
+            0 => "yes".to_string(),
+            1 => "no".to_string(),
+            n => format!("outcome_{n}"),
         }
     }
 }
@@ -36,35 +50,55 @@ impl OrderAction {
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum OrderStatus {
     Pending,
+    PartiallyFilled,
     Filled,
     Cancelled,
 }
 
+/// How long a resting order stays live.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimeInForce {
+    /// Good-till-cancelled: rests until filled or explicitly cancelled.
+    Gtc,
+    /// Good-till-time: the broker auto-cancels it once `Trade::timestamp`
+    /// reaches this epoch-seconds expiry, even if never filled.
+    Gtt(f64),
+}
+
 /// Trade event extracted from a Python TradeEvent.
 #[derive(Clone, Debug)]
 pub struct Trade {
     pub timestamp: f64, // epoch seconds
     pub market_id: String,
-    pub yes_price: f64,
-    pub no_price: f64,
-    pub quantity: f64,
+    /// Price of each outcome at the time of the trade, indexed by outcome id.
+    /// Binary markets carry exactly two entries: `[yes_price, no_price]`.
+    pub prices: Vec<Fixed>,
+    pub quantity: Fixed,
     pub taker_side: Side,
 }
 
-/// Limit order managed by the broker.
+/// Limit order managed by the broker. Always targets a single outcome;
+/// combinatorial bets are expressed as several `Order`s via `OutcomePartition`.
 #[derive(Clone, Debug)]
 pub struct Order {
     pub order_id: String,
     pub market_id: String,
     pub action: OrderAction,
     pub side: Side,
-    pub price: f64,
-    pub quantity: f64,
+    pub price: Fixed,
+    pub quantity: Fixed,
     pub status: OrderStatus,
     pub created_at: f64,
+    pub time_in_force: TimeInForce,
     pub filled_at: Option<f64>,
-    pub fill_price: Option<f64>,
-    pub filled_quantity: f64,
+    /// Volume-weighted average price across all fills received so far.
+    pub fill_price: Option<Fixed>,
+    pub filled_quantity: Fixed,
+    /// Tag shared by every order placed in the same batch (e.g. one
+    /// `Broker::place_market_making_quotes` ladder), so they can be
+    /// cancelled together via `Broker::cancel_all(CancelScope::Group(..))`
+    /// without disturbing other resting orders in the same market.
+    pub group_id: Option<String>,
 }
 
 /// Record of a filled order.
@@ -74,40 +108,72 @@ pub struct Fill {
     pub market_id: String,
     pub action: OrderAction,
     pub side: Side,
-    pub price: f64,
-    pub quantity: f64,
+    pub price: Fixed,
+    pub quantity: Fixed,
     pub timestamp: f64,
-    pub commission: f64,
+    pub commission: Fixed,
+}
+
+/// Per-outcome holding within a `Position`: a signed quantity (positive =
+/// long the outcome, negative = short it) and its volume-weighted average
+/// entry price.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OutcomeHolding {
+    pub quantity: Fixed,
+    pub avg_entry_price: Fixed,
 }
 
-/// Position in a single market.
+/// Position in a single market, generalized over an arbitrary outcome set.
+///
+/// Holdings are tracked independently per outcome id so that categorical
+/// and combinatorial bets (see `OutcomePartition`) compose naturally; a
+/// binary YES/NO position is just the two-entry case.
 #[derive(Clone, Debug)]
 pub struct Position {
     pub market_id: String,
-    pub quantity: f64,
-    pub avg_entry_price: f64,
-    pub realized_pnl: f64,
+    pub outcomes: HashMap<usize, OutcomeHolding>,
+    pub realized_pnl: Fixed,
+    /// Number of times `Portfolio::apply_adjustment` has scaled this
+    /// position via a DCA/position-adjustment hook, bounded by
+    /// `Portfolio::max_position_adjustments`.
+    pub adjustment_count: u32,
 }
 
 impl Position {
     pub fn new(market_id: String) -> Self {
         Self {
             market_id,
-            quantity: 0.0,
-            avg_entry_price: 0.0,
-            realized_pnl: 0.0,
+            outcomes: HashMap::new(),
+            realized_pnl: Fixed::ZERO,
+            adjustment_count: 0,
         }
     }
+
+    pub fn quantity(&self, side: Side) -> Fixed {
+        self.outcomes.get(&side.0).map_or(Fixed::ZERO, |h| h.quantity)
+    }
+
+    pub fn avg_entry_price(&self, side: Side) -> Fixed {
+        self.outcomes.get(&side.0).map_or(Fixed::ZERO, |h| h.avg_entry_price)
+    }
+
+    pub fn is_flat(&self) -> bool {
+        self.outcomes.values().all(|h| h.quantity.is_zero())
+    }
 }
 
 /// Point-in-time portfolio snapshot.
 #[derive(Clone, Debug)]
 pub struct Snapshot {
     pub timestamp: f64,
-    pub cash: f64,
-    pub total_equity: f64,
-    pub unrealized_pnl: f64,
+    pub cash: Fixed,
+    pub total_equity: Fixed,
+    pub unrealized_pnl: Fixed,
     pub num_positions: i32,
+    /// Account health: cash + unrealized PnL − maintenance collateral
+    /// required across all open positions. Negative means the account is
+    /// eligible for forced liquidation (see `Portfolio::liquidate_unhealthy`).
+    pub health: Fixed,
 }
 
 /// Extracted market metadata for lifecycle event processing.
@@ -119,3 +185,75 @@ pub struct MarketData {
     pub close_time: Option<f64>,
     pub result: Option<Side>,
 }
+
+/// Reason an `OutcomePartition` failed validation, or a combinatorial
+/// order built from one couldn't be priced.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PartitionError {
+    EmptyBuyGroup,
+    EmptySellGroup,
+    OverlappingGroups,
+    IncompleteUnion,
+    /// `prices` didn't carry an entry for every outcome in the partition,
+    /// so a leg couldn't be priced — rejected rather than silently priced
+    /// at `Fixed::ZERO`, which would place a free-giveaway order.
+    MissingPrice,
+}
+
+/// Reason `Portfolio::apply_adjustment` refused to scale a position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AdjustmentError {
+    /// The position has already received `max_position_adjustments`
+    /// scale-ins/scale-outs; a runaway DCA hook can't push further.
+    AdjustmentCapReached,
+}
+
+/// A combinatorial order expressed as a partition of a market's outcome
+/// set into buy, sell, and keep groups.
+///
+/// Combinatorial prediction markets settle on the rule that holding one
+/// contract of every outcome is worth exactly $1 at resolution, so going
+/// long the `buy` group and short the `sell` group is self-financing: the
+/// `keep` group is simply left untouched. A partition must cover every
+/// outcome in the market exactly once — that's what `validate` checks.
+#[derive(Clone, Debug)]
+pub struct OutcomePartition {
+    pub buy: Vec<usize>,
+    pub sell: Vec<usize>,
+    pub keep: Vec<usize>,
+    pub num_outcomes: usize,
+}
+
+impl OutcomePartition {
+    pub fn new(buy: Vec<usize>, sell: Vec<usize>, keep: Vec<usize>, num_outcomes: usize) -> Self {
+        Self {
+            buy,
+            sell,
+            keep,
+            num_outcomes,
+        }
+    }
+
+    /// Reject empty buy/sell groups, overlapping groups, and partitions
+    /// that don't cover every outcome in the market exactly once.
+    pub fn validate(&self) -> Result<(), PartitionError> {
+        if self.buy.is_empty() {
+            return Err(PartitionError::EmptyBuyGroup);
+        }
+        if self.sell.is_empty() {
+            return Err(PartitionError::EmptySellGroup);
+        }
+
+        let mut seen = vec![false; self.num_outcomes];
+        for &idx in self.buy.iter().chain(self.sell.iter()).chain(self.keep.iter()) {
+            if idx >= self.num_outcomes || seen[idx] {
+                return Err(PartitionError::OverlappingGroups);
+            }
+            seen[idx] = true;
+        }
+        if seen.iter().any(|&covered| !covered) {
+            return Err(PartitionError::IncompleteUnion);
+        }
+        Ok(())
+    }
+}
@@ -1,23 +1,46 @@
 /// Portfolio tracking — positions, cash, equity snapshots.
 ///
-/// Direct port of portfolio.py with identical accounting logic.
-/// Positions stored as yes-contract quantity: +qty = long YES, -qty = long NO.
+/// Direct port of portfolio.py with identical accounting logic, generalized
+/// from binary yes-contract quantities to a per-outcome quantity map so
+/// categorical and combinatorial markets settle the same way binary ones do.
+/// All monetary and quantity figures are `Fixed` so a long backtest can't
+/// drift from floating-point rounding.
 
 use std::collections::{HashMap, HashSet};
 
-use crate::models::{Fill, OrderAction, Position, Side, Snapshot};
+use crate::broker::Broker;
+use crate::fixed::Fixed;
+use crate::models::{
+    AdjustmentError, Fill, Order, OrderAction, Position, Side, Snapshot, TimeInForce, Trade,
+};
 
 pub struct Portfolio {
-    pub cash: f64,
-    pub initial_cash: f64,
+    pub cash: Fixed,
+    pub initial_cash: Fixed,
     pub positions: HashMap<String, Position>,
-    pub last_prices: HashMap<String, f64>,
+    /// Latest observed price per outcome, keyed by market id then outcome id.
+    pub last_prices: HashMap<String, HashMap<usize, Fixed>>,
     pub snapshots: Vec<Snapshot>,
     resolved_markets: HashSet<String>,
+    /// Fraction of a position's notional held as maintenance collateral.
+    /// E.g. 0.1 means a $100 notional position requires $10 of collateral,
+    /// letting the rest of its cost be financed on margin.
+    pub maintenance_margin_ratio: Fixed,
+    /// Extra penalty charged against realized PnL (on top of normal close
+    /// accounting) when `liquidate_unhealthy` force-closes a position.
+    pub liquidation_penalty_rate: Fixed,
+    /// Cap on how many times `apply_adjustment` may scale a single
+    /// position, so a runaway DCA hook can't adjust it indefinitely.
+    pub max_position_adjustments: u32,
 }
 
 impl Portfolio {
-    pub fn new(initial_cash: f64) -> Self {
+    pub fn new(
+        initial_cash: Fixed,
+        maintenance_margin_ratio: Fixed,
+        liquidation_penalty_rate: Fixed,
+        max_position_adjustments: u32,
+    ) -> Self {
         Self {
             cash: initial_cash,
             initial_cash,
@@ -25,6 +48,9 @@ impl Portfolio {
             last_prices: HashMap::new(),
             snapshots: Vec::new(),
             resolved_markets: HashSet::new(),
+            maintenance_margin_ratio,
+            liquidation_penalty_rate,
+            max_position_adjustments,
         }
     }
 
@@ -34,23 +60,13 @@ impl Portfolio {
             .entry(fill.market_id.clone())
             .or_insert_with(|| Position::new(fill.market_id.clone()));
 
-        match (fill.action, fill.side) {
-            (OrderAction::Buy, Side::Yes) => {
-                Self::add_to_position(pos, fill.quantity, fill.price);
+        match fill.action {
+            OrderAction::Buy => {
+                Self::add_to_position(pos, fill.side, fill.quantity, fill.price);
                 self.cash -= fill.price * fill.quantity;
             }
-            (OrderAction::Sell, Side::Yes) => {
-                Self::reduce_position(pos, fill.quantity, fill.price);
-                self.cash += fill.price * fill.quantity;
-            }
-            (OrderAction::Buy, Side::No) => {
-                let yes_equiv = 1.0 - fill.price;
-                Self::add_to_position(pos, -fill.quantity, yes_equiv);
-                self.cash -= fill.price * fill.quantity;
-            }
-            (OrderAction::Sell, Side::No) => {
-                let yes_equiv = 1.0 - fill.price;
-                Self::reduce_position(pos, -fill.quantity, yes_equiv);
+            OrderAction::Sell => {
+                Self::reduce_position(pos, fill.side, fill.quantity, fill.price);
                 self.cash += fill.price * fill.quantity;
             }
         }
@@ -58,54 +74,72 @@ impl Portfolio {
         self.cash -= fill.commission;
     }
 
-    pub fn resolve_market(&mut self, market_id: &str, result: Side) -> f64 {
+    /// Pay out $1 to the winning outcome and $0 elsewhere, across every
+    /// outcome component of the position — this is what makes combinatorial
+    /// baskets settle correctly, not just a single binary YES/NO leg.
+    pub fn resolve_market(&mut self, market_id: &str, winning_side: Side) -> Fixed {
         if self.resolved_markets.contains(market_id) {
-            return 0.0;
+            return Fixed::ZERO;
         }
         let pos = match self.positions.get_mut(market_id) {
             Some(p) => p,
             None => {
                 self.resolved_markets.insert(market_id.to_string());
-                return 0.0;
+                return Fixed::ZERO;
             }
         };
-        if pos.quantity == 0.0 {
+        if pos.is_flat() {
             self.resolved_markets.insert(market_id.to_string());
-            return 0.0;
+            return Fixed::ZERO;
         }
 
-        let settlement = if result == Side::Yes { 1.0 } else { 0.0 };
+        let one = Fixed::ONE;
+        let mut total_pnl = Fixed::ZERO;
+        for (&outcome_idx, holding) in pos.outcomes.iter_mut() {
+            if holding.quantity.is_zero() {
+                continue;
+            }
+            let settlement = if outcome_idx == winning_side.index() {
+                one
+            } else {
+                Fixed::ZERO
+            };
 
-        // Long YES (qty > 0): get qty * settlement ($1 if YES, $0 if NO)
-        // Long NO  (qty < 0): get |qty| * (1 - settlement) ($1 if NO, $0 if YES)
-        let payout = if pos.quantity > 0.0 {
-            pos.quantity * settlement
-        } else {
-            pos.quantity.abs() * (1.0 - settlement)
-        };
-        self.cash += payout;
+            // `holding.quantity` is signed, so this settles long and short
+            // holdings uniformly: a long (qty > 0) collects qty * settlement,
+            // while a short (qty < 0) owes |qty| * settlement back against
+            // the premium it already collected into cash at trade time —
+            // exactly what qty * settlement works out to when qty is
+            // negative. No separate short-side formula needed.
+            let payout = holding.quantity * settlement;
+            self.cash += payout;
 
-        let cost_basis = if pos.quantity > 0.0 {
-            pos.quantity * pos.avg_entry_price
-        } else {
-            pos.quantity.abs() * (1.0 - pos.avg_entry_price)
-        };
-        let resolution_pnl = payout - cost_basis;
-        pos.realized_pnl += resolution_pnl;
+            let cost_basis = holding.quantity * holding.avg_entry_price;
+            total_pnl += payout - cost_basis;
 
-        pos.quantity = 0.0;
-        pos.avg_entry_price = 0.0;
-        self.resolved_markets.insert(market_id.to_string());
+            holding.quantity = Fixed::ZERO;
+            holding.avg_entry_price = Fixed::ZERO;
+        }
 
-        resolution_pnl
+        pos.realized_pnl += total_pnl;
+        self.resolved_markets.insert(market_id.to_string());
+        total_pnl
     }
 
-    pub fn update_price(&mut self, market_id: &str, yes_price: f64) {
-        self.last_prices.insert(market_id.to_string(), yes_price);
+    pub fn update_price(&mut self, market_id: &str, side: Side, price: Fixed) {
+        self.last_prices
+            .entry(market_id.to_string())
+            .or_default()
+            .insert(side.index(), price);
     }
 
-    /// Compute and store a snapshot.
+    /// Compute and store a snapshot, force-liquidating first if health has
+    /// gone negative since the last one — the "snapshot processing" half
+    /// of the margin system (`process_trade` covers the check-fills half).
     pub fn snapshot(&mut self, timestamp: f64) -> Snapshot {
+        if self.health() < Fixed::ZERO {
+            self.liquidate_unhealthy(self.liquidation_penalty_rate);
+        }
         let snap = self.compute_snapshot(timestamp);
         self.snapshots.push(snap.clone());
         snap
@@ -113,25 +147,7 @@ impl Portfolio {
 
     /// Compute a snapshot without storing it.
     pub fn compute_snapshot(&self, timestamp: f64) -> Snapshot {
-        let mut unrealized = 0.0;
-        let mut num_positions = 0i32;
-
-        for (mid, pos) in &self.positions {
-            if pos.quantity == 0.0 || self.resolved_markets.contains(mid) {
-                continue;
-            }
-            num_positions += 1;
-            let last_price = self
-                .last_prices
-                .get(mid)
-                .copied()
-                .unwrap_or(pos.avg_entry_price);
-            if pos.quantity > 0.0 {
-                unrealized += pos.quantity * (last_price - pos.avg_entry_price);
-            } else {
-                unrealized += pos.quantity.abs() * (pos.avg_entry_price - last_price);
-            }
-        }
+        let (unrealized, num_positions, collateral) = self.unrealized_and_collateral();
 
         Snapshot {
             timestamp,
@@ -139,6 +155,7 @@ impl Portfolio {
             total_equity: self.cash + unrealized,
             unrealized_pnl: unrealized,
             num_positions,
+            health: self.cash + unrealized - collateral,
         }
     }
 
@@ -146,52 +163,486 @@ impl Portfolio {
         self.resolved_markets.contains(market_id)
     }
 
-    fn add_to_position(pos: &mut Position, delta: f64, price: f64) {
-        if pos.quantity == 0.0 {
-            pos.quantity = delta;
-            pos.avg_entry_price = price;
+    /// Account health: cash + unrealized PnL − maintenance collateral
+    /// required across all open positions. Negative health means the
+    /// account is eligible for forced liquidation.
+    pub fn health(&self) -> Fixed {
+        let (unrealized, _, collateral) = self.unrealized_and_collateral();
+        self.cash + unrealized - collateral
+    }
+
+    /// Whether placing this order would keep the account's health
+    /// non-negative, used to reject orders at placement time rather than
+    /// letting them push the portfolio into forced-liquidation territory.
+    /// Accounts for the order's effect on `market_id`/`side`'s own
+    /// collateral requirement, so a sell that reduces (or flips) existing
+    /// exposure frees collateral instead of being charged for more of it
+    /// on top of what the position already holds.
+    pub fn would_stay_healthy(
+        &self,
+        market_id: &str,
+        side: Side,
+        action: OrderAction,
+        price: Fixed,
+        quantity: Fixed,
+    ) -> bool {
+        let (unrealized, _, collateral) = self.unrealized_and_collateral();
+        let notional = price * quantity;
+        let projected_cash = match action {
+            OrderAction::Buy => self.cash - notional,
+            OrderAction::Sell => self.cash + notional,
+        };
+
+        let existing_qty = self.positions.get(market_id).map_or(Fixed::ZERO, |pos| pos.quantity(side));
+        let avg_entry_price = self.positions.get(market_id).map_or(Fixed::ZERO, |pos| pos.avg_entry_price(side));
+        let last_price = self
+            .last_prices
+            .get(market_id)
+            .and_then(|prices| prices.get(&side.index()))
+            .copied()
+            .unwrap_or(avg_entry_price);
+
+        let signed_delta = if action == OrderAction::Buy { quantity } else { -quantity };
+        let current_leg_collateral = existing_qty.abs() * last_price * self.maintenance_margin_ratio;
+        let projected_leg_collateral =
+            (existing_qty + signed_delta).abs() * last_price * self.maintenance_margin_ratio;
+        let projected_collateral = collateral - current_leg_collateral + projected_leg_collateral;
+
+        projected_cash + unrealized - projected_collateral >= Fixed::ZERO
+    }
+
+    /// Place an order through `broker`, rejecting it (returning `None`)
+    /// if it would push account health negative instead of letting it
+    /// through and relying on `liquidate_unhealthy` to unwind it later.
+    /// This is the placement-time half of the margin system, and the one
+    /// gate every order-entry point (rebalancer, market-making ladder,
+    /// combinatorial order) must go through rather than calling
+    /// `Broker::place_order` directly.
+    #[allow(clippy::too_many_arguments)]
+    pub fn place_order_if_healthy(
+        &self,
+        broker: &mut Broker,
+        market_id: &str,
+        action: OrderAction,
+        side: Side,
+        price: Fixed,
+        quantity: Fixed,
+        timestamp: f64,
+        time_in_force: TimeInForce,
+        group_id: Option<String>,
+    ) -> Option<Order> {
+        if !self.would_stay_healthy(market_id, side, action, price, quantity) {
+            return None;
+        }
+        Some(broker.place_order(market_id, action, side, price, quantity, timestamp, time_in_force, group_id))
+    }
+
+    /// Check `trade` against `broker`'s pending orders, apply the resulting
+    /// fills, and force-liquidate if they pushed account health negative —
+    /// the check-fills half of the margin system (`snapshot` covers the
+    /// snapshot-processing half). Returns the fills, any GTT expirations,
+    /// and the market ids force-liquidated, in that order.
+    pub fn process_trade(&mut self, broker: &mut Broker, trade: &Trade) -> (Vec<Fill>, Vec<Order>, Vec<String>) {
+        let (fills, expired) = broker.check_fills(trade, self.cash);
+        for fill in &fills {
+            self.apply_fill(fill);
+        }
+        let liquidated = if self.health() < Fixed::ZERO {
+            self.liquidate_unhealthy(self.liquidation_penalty_rate)
+        } else {
+            Vec::new()
+        };
+        (fills, expired, liquidated)
+    }
+
+    /// Unrealized PnL of a single outcome within a position, at its last
+    /// observed price. Feeds a per-snapshot position-adjustment hook
+    /// alongside `Position::quantity`/`avg_entry_price`, so the hook can
+    /// decide whether to scale into or out of the position.
+    pub fn unrealized_pnl_for_side(&self, market_id: &str, side: Side) -> Fixed {
+        let Some(holding) = self
+            .positions
+            .get(market_id)
+            .and_then(|pos| pos.outcomes.get(&side.index()))
+        else {
+            return Fixed::ZERO;
+        };
+        if holding.quantity.is_zero() {
+            return Fixed::ZERO;
+        }
+
+        let last_price = self
+            .last_prices
+            .get(market_id)
+            .and_then(|prices| prices.get(&side.index()))
+            .copied()
+            .unwrap_or(holding.avg_entry_price);
+        if holding.quantity > Fixed::ZERO {
+            holding.quantity * (last_price - holding.avg_entry_price)
+        } else {
+            holding.quantity.abs() * (holding.avg_entry_price - last_price)
+        }
+    }
+
+    /// Apply a signed quantity delta to a position at `price`, as decided
+    /// by an external per-snapshot adjustment hook (e.g. a DCA strategy
+    /// callback fed by `unrealized_pnl_for_side` and `Position`'s own
+    /// accessors). A positive delta averages into the position via the
+    /// same weighted-average-cost logic `apply_fill` uses for a buy fill;
+    /// a negative delta partially closes it via the same proportional-PnL-
+    /// realization logic used for a sell fill. Refuses once the position
+    /// has been adjusted `max_position_adjustments` times. Returns the
+    /// realized PnL booked by this adjustment.
+    pub fn apply_adjustment(
+        &mut self,
+        market_id: &str,
+        side: Side,
+        delta: Fixed,
+        price: Fixed,
+    ) -> Result<Fixed, AdjustmentError> {
+        let pos = self
+            .positions
+            .entry(market_id.to_string())
+            .or_insert_with(|| Position::new(market_id.to_string()));
+
+        if pos.adjustment_count >= self.max_position_adjustments {
+            return Err(AdjustmentError::AdjustmentCapReached);
+        }
+        if delta.is_zero() {
+            return Ok(Fixed::ZERO);
+        }
+
+        let realized_before = pos.realized_pnl;
+        if delta > Fixed::ZERO {
+            Self::add_to_position(pos, side, delta, price);
+        } else {
+            Self::close_partial(pos, side, delta, price);
+        }
+        let realized_delta = pos.realized_pnl - realized_before;
+        pos.adjustment_count += 1;
+
+        self.cash -= delta * price;
+        Ok(realized_delta)
+    }
+
+    /// Force-liquidate the worst-performing open positions, worst first, at
+    /// their last-known price (plus a liquidation penalty charged against
+    /// realized PnL) until health is restored or the account is flat.
+    /// Returns the market ids closed, in the order they were liquidated.
+    pub fn liquidate_unhealthy(&mut self, penalty_rate: Fixed) -> Vec<String> {
+        let mut liquidated = Vec::new();
+        while self.health() < Fixed::ZERO {
+            let market_id = match self.worst_performing_market() {
+                Some(mid) => mid,
+                None => break,
+            };
+            self.liquidate_market(&market_id, penalty_rate);
+            liquidated.push(market_id);
+        }
+        liquidated
+    }
+
+    /// Sum of unrealized PnL, open-position count, and required maintenance
+    /// collateral across every open, unresolved position. Shared by
+    /// `compute_snapshot`, `health`, and `would_stay_healthy` so they can't
+    /// drift out of sync.
+    fn unrealized_and_collateral(&self) -> (Fixed, i32, Fixed) {
+        let mut unrealized = Fixed::ZERO;
+        let mut num_positions = 0i32;
+        let mut collateral = Fixed::ZERO;
+
+        for (mid, pos) in &self.positions {
+            if pos.is_flat() || self.resolved_markets.contains(mid) {
+                continue;
+            }
+            num_positions += 1;
+            let market_prices = self.last_prices.get(mid);
+
+            for (&outcome_idx, holding) in &pos.outcomes {
+                if holding.quantity.is_zero() {
+                    continue;
+                }
+                let last_price = market_prices
+                    .and_then(|prices| prices.get(&outcome_idx))
+                    .copied()
+                    .unwrap_or(holding.avg_entry_price);
+                if holding.quantity > Fixed::ZERO {
+                    unrealized += holding.quantity * (last_price - holding.avg_entry_price);
+                } else {
+                    unrealized += holding.quantity.abs() * (holding.avg_entry_price - last_price);
+                }
+                collateral += holding.quantity.abs() * last_price * self.maintenance_margin_ratio;
+            }
+        }
+
+        (unrealized, num_positions, collateral)
+    }
+
+    /// The open, unresolved position with the worst unrealized PnL — the
+    /// first candidate for forced liquidation. Ties break on `market_id` so
+    /// the choice doesn't depend on `HashMap`'s randomized iteration order.
+    fn worst_performing_market(&self) -> Option<String> {
+        self.positions
+            .iter()
+            .filter(|(mid, pos)| !pos.is_flat() && !self.resolved_markets.contains(*mid))
+            .map(|(mid, pos)| (mid.clone(), self.unrealized_pnl_for(mid, pos)))
+            .min_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)))
+            .map(|(mid, _)| mid)
+    }
+
+    fn unrealized_pnl_for(&self, market_id: &str, pos: &Position) -> Fixed {
+        let market_prices = self.last_prices.get(market_id);
+        pos.outcomes
+            .iter()
+            .map(|(&outcome_idx, holding)| {
+                if holding.quantity.is_zero() {
+                    return Fixed::ZERO;
+                }
+                let last_price = market_prices
+                    .and_then(|prices| prices.get(&outcome_idx))
+                    .copied()
+                    .unwrap_or(holding.avg_entry_price);
+                if holding.quantity > Fixed::ZERO {
+                    holding.quantity * (last_price - holding.avg_entry_price)
+                } else {
+                    holding.quantity.abs() * (holding.avg_entry_price - last_price)
+                }
+            })
+            .sum()
+    }
+
+    /// Force-close every outcome held in `market_id` at its last-known
+    /// price, charging an extra liquidation penalty on top of the usual
+    /// close accounting.
+    fn liquidate_market(&mut self, market_id: &str, penalty_rate: Fixed) {
+        let prices = self.last_prices.get(market_id).cloned().unwrap_or_default();
+        let Some(pos) = self.positions.get_mut(market_id) else {
+            return;
+        };
+
+        let mut cash_delta = Fixed::ZERO;
+        for (outcome_idx, holding) in pos.outcomes.iter_mut() {
+            if holding.quantity.is_zero() {
+                continue;
+            }
+            let price = prices.get(outcome_idx).copied().unwrap_or(holding.avg_entry_price);
+            let closing_qty = holding.quantity.abs();
+
+            let pnl = if holding.quantity > Fixed::ZERO {
+                closing_qty * (price - holding.avg_entry_price)
+            } else {
+                closing_qty * (holding.avg_entry_price - price)
+            };
+            let penalty = closing_qty * price * penalty_rate;
+            pos.realized_pnl += pnl - penalty;
+            cash_delta += if holding.quantity > Fixed::ZERO {
+                closing_qty * price
+            } else {
+                -(closing_qty * price)
+            };
+            cash_delta -= penalty;
+
+            holding.quantity = Fixed::ZERO;
+            holding.avg_entry_price = Fixed::ZERO;
+        }
+        self.cash += cash_delta;
+    }
+
+    fn add_to_position(pos: &mut Position, side: Side, delta: Fixed, price: Fixed) {
+        let holding = pos.outcomes.entry(side.index()).or_default();
+        if holding.quantity.is_zero() {
+            holding.quantity = delta;
+            holding.avg_entry_price = price;
             return;
         }
 
-        let same_direction = (pos.quantity > 0.0) == (delta > 0.0);
+        let same_direction = (holding.quantity > Fixed::ZERO) == (delta > Fixed::ZERO);
         if same_direction {
-            let total_cost = pos.quantity.abs() * pos.avg_entry_price + delta.abs() * price;
-            pos.quantity += delta;
-            if pos.quantity != 0.0 {
-                pos.avg_entry_price = total_cost / pos.quantity.abs();
+            let total_cost = holding.quantity.abs() * holding.avg_entry_price + delta.abs() * price;
+            holding.quantity += delta;
+            if !holding.quantity.is_zero() {
+                holding.avg_entry_price = total_cost / holding.quantity.abs();
             }
         } else {
-            Self::close_partial(pos, delta, price);
+            Self::close_partial(pos, side, delta, price);
         }
     }
 
-    fn reduce_position(pos: &mut Position, delta: f64, price: f64) {
-        Self::close_partial(pos, -delta, price);
+    fn reduce_position(pos: &mut Position, side: Side, delta: Fixed, price: Fixed) {
+        Self::close_partial(pos, side, -delta, price);
     }
 
-    fn close_partial(pos: &mut Position, delta: f64, price: f64) {
-        let closing_qty = delta.abs().min(pos.quantity.abs());
-        if closing_qty == 0.0 {
-            pos.quantity += delta;
-            pos.avg_entry_price = price;
+    fn close_partial(pos: &mut Position, side: Side, delta: Fixed, price: Fixed) {
+        let holding = pos.outcomes.entry(side.index()).or_default();
+        let closing_qty = delta.abs().min(holding.quantity.abs());
+        if closing_qty.is_zero() {
+            holding.quantity += delta;
+            holding.avg_entry_price = price;
             return;
         }
 
-        let pnl = if pos.quantity > 0.0 {
-            closing_qty * (price - pos.avg_entry_price)
+        let pnl = if holding.quantity > Fixed::ZERO {
+            closing_qty * (price - holding.avg_entry_price)
         } else {
-            closing_qty * (pos.avg_entry_price - price)
+            closing_qty * (holding.avg_entry_price - price)
         };
         pos.realized_pnl += pnl;
 
         let remaining = delta.abs() - closing_qty;
-        pos.quantity += delta;
+        holding.quantity += delta;
+
+        // Fixed-point arithmetic is exact, so a fully-closed holding lands
+        // on precisely zero — no epsilon tolerance needed here.
+        if holding.quantity.is_zero() {
+            holding.avg_entry_price = Fixed::ZERO;
+        } else if remaining > Fixed::ZERO {
+            holding.avg_entry_price = price;
+        }
+    }
+}
 
-        if pos.quantity.abs() < 1e-10 {
-            pos.quantity = 0.0;
-            pos.avg_entry_price = 0.0;
-        } else if remaining > 0.0 {
-            pos.avg_entry_price = price;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(market_id: &str, action: OrderAction, side: Side, price: f64, quantity: f64) -> Fill {
+        Fill {
+            order_id: "1".to_string(),
+            market_id: market_id.to_string(),
+            action,
+            side,
+            price: Fixed::from_f64(price),
+            quantity: Fixed::from_f64(quantity),
+            timestamp: 0.0,
+            commission: Fixed::ZERO,
         }
     }
+
+    #[test]
+    fn resolve_market_pays_long_holder_on_win_and_nothing_on_loss() {
+        let mut won = Portfolio::new(Fixed::from_f64(1000.0), Fixed::ZERO, Fixed::ZERO, 0);
+        won.apply_fill(&fill("m", OrderAction::Buy, Side::YES, 0.30, 10.0));
+        let pnl = won.resolve_market("m", Side::YES);
+        assert_eq!(pnl, 7.0);
+        assert_eq!(won.cash, 1007.0);
+
+        let mut lost = Portfolio::new(Fixed::from_f64(1000.0), Fixed::ZERO, Fixed::ZERO, 0);
+        lost.apply_fill(&fill("m", OrderAction::Buy, Side::YES, 0.30, 10.0));
+        let pnl = lost.resolve_market("m", Side::NO);
+        assert_eq!(pnl, -3.0);
+        assert_eq!(lost.cash, 997.0);
+    }
+
+    /// Regression test for a bug where a naked short's settlement cash flow
+    /// reused the binary-only "1 - settlement" complement trick and ended
+    /// up fabricating cash equal to the short's notional (see resolve_market).
+    #[test]
+    fn resolve_market_settles_naked_short_without_fabricating_cash() {
+        let mut won = Portfolio::new(Fixed::from_f64(1000.0), Fixed::ZERO, Fixed::ZERO, 0);
+        won.apply_fill(&fill("m", OrderAction::Sell, Side::YES, 0.30, 10.0));
+        let pnl = won.resolve_market("m", Side::YES);
+        assert_eq!(pnl, -7.0);
+        assert_eq!(won.cash, 993.0);
+
+        let mut lost = Portfolio::new(Fixed::from_f64(1000.0), Fixed::ZERO, Fixed::ZERO, 0);
+        lost.apply_fill(&fill("m", OrderAction::Sell, Side::YES, 0.30, 10.0));
+        let pnl = lost.resolve_market("m", Side::NO);
+        assert_eq!(pnl, 3.0);
+        assert_eq!(lost.cash, 1003.0);
+    }
+
+    #[test]
+    fn process_trade_applies_fills_and_force_liquidates_on_negative_health() {
+        let mut broker = Broker::new(Fixed::ZERO, Fixed::ZERO, false, Fixed::from_f64(0.1));
+        let mut portfolio = Portfolio::new(Fixed::from_f64(1000.0), Fixed::from_f64(20.0), Fixed::ZERO, 0);
+        broker.place_order(
+            "m",
+            OrderAction::Buy,
+            Side::YES,
+            Fixed::from_f64(0.50),
+            Fixed::from_f64(100.0),
+            0.0,
+            TimeInForce::Gtc,
+            None,
+        );
+
+        let trade = Trade {
+            timestamp: 0.0,
+            market_id: "m".to_string(),
+            prices: vec![Fixed::from_f64(0.50), Fixed::from_f64(0.50)],
+            quantity: Fixed::from_f64(100.0),
+            taker_side: Side::YES,
+        };
+        let (fills, expired, liquidated) = portfolio.process_trade(&mut broker, &trade);
+
+        assert_eq!(fills.len(), 1);
+        assert!(expired.is_empty());
+        assert_eq!(liquidated, vec!["m".to_string()]);
+        assert!(portfolio.positions.get("m").unwrap().is_flat());
+        assert_eq!(portfolio.cash, 1000.0);
+    }
+
+    #[test]
+    fn worst_performing_market_breaks_ties_by_market_id() {
+        let mut portfolio = Portfolio::new(Fixed::from_f64(10.0), Fixed::from_f64(1.0), Fixed::ZERO, 0);
+        portfolio.apply_fill(&fill("b", OrderAction::Buy, Side::YES, 0.50, 10.0));
+        portfolio.apply_fill(&fill("a", OrderAction::Buy, Side::YES, 0.50, 10.0));
+
+        // Both positions carry zero unrealized PnL (no price update since
+        // entry), so this is a pure tie broken only by market_id.
+        let liquidated = portfolio.liquidate_unhealthy(Fixed::ZERO);
+        assert_eq!(liquidated[0], "a");
+    }
+
+    #[test]
+    fn would_stay_healthy_treats_a_de_risking_sell_as_freeing_collateral() {
+        let mut portfolio = Portfolio::new(Fixed::from_f64(50.0), Fixed::from_f64(1.0), Fixed::ZERO, 0);
+        portfolio.apply_fill(&fill("m", OrderAction::Buy, Side::YES, 0.50, 100.0));
+        assert_eq!(portfolio.health(), -50.0);
+
+        // Selling down the exact position frees its collateral rather than
+        // piling flat notional-based collateral on top of what it already
+        // holds, so this sell should be allowed even though health is
+        // currently negative.
+        assert!(portfolio.would_stay_healthy(
+            "m",
+            Side::YES,
+            OrderAction::Sell,
+            Fixed::from_f64(0.50),
+            Fixed::from_f64(100.0),
+        ));
+
+        let mut broker = Broker::new(Fixed::ZERO, Fixed::ZERO, false, Fixed::from_f64(0.1));
+        let order = portfolio.place_order_if_healthy(
+            &mut broker,
+            "m",
+            OrderAction::Sell,
+            Side::YES,
+            Fixed::from_f64(0.50),
+            Fixed::from_f64(100.0),
+            0.0,
+            TimeInForce::Gtc,
+            None,
+        );
+        assert!(order.is_some());
+    }
+
+    #[test]
+    fn apply_adjustment_scales_position_and_enforces_the_adjustment_cap() {
+        let mut portfolio = Portfolio::new(Fixed::from_f64(1000.0), Fixed::ZERO, Fixed::ZERO, 1);
+
+        let realized = portfolio
+            .apply_adjustment("m", Side::YES, Fixed::from_f64(10.0), Fixed::from_f64(0.40))
+            .unwrap();
+        assert_eq!(realized, 0.0);
+        assert_eq!(portfolio.positions.get("m").unwrap().quantity(Side::YES), 10.0);
+        assert_eq!(portfolio.cash, 996.0);
+
+        let err = portfolio
+            .apply_adjustment("m", Side::YES, Fixed::from_f64(5.0), Fixed::from_f64(0.40))
+            .unwrap_err();
+        assert_eq!(err, AdjustmentError::AdjustmentCapReached);
+        // Position is untouched by the rejected adjustment.
+        assert_eq!(portfolio.positions.get("m").unwrap().quantity(Side::YES), 10.0);
+    }
 }
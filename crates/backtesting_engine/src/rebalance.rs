@@ -0,0 +1,151 @@
+/// Weight-targeted portfolio rebalancing.
+///
+/// Turns a set of target outcome weights (fraction of total equity) into
+/// the buy/sell orders needed to move the portfolio toward them, so a
+/// strategy can call this periodically instead of hand-computing position
+/// sizing on every snapshot.
+
+use crate::broker::Broker;
+use crate::fixed::Fixed;
+use crate::models::{Order, OrderAction, Side, TimeInForce};
+use crate::portfolio::Portfolio;
+
+/// Target allocation for a single outcome, as a fraction of total equity.
+#[derive(Clone, Debug)]
+pub struct RebalanceTarget {
+    pub market_id: String,
+    pub side: Side,
+    pub weight: Fixed,
+}
+
+pub struct Rebalancer {
+    /// Adjustments smaller than this notional are skipped so the backtest
+    /// doesn't churn on tiny deltas.
+    pub min_trade_volume: Fixed,
+}
+
+impl Rebalancer {
+    pub fn new(min_trade_volume: Fixed) -> Self {
+        Self { min_trade_volume }
+    }
+
+    /// Compute the delta between current positions (valued at
+    /// `portfolio.last_prices`) and `targets`, and place the orders needed
+    /// to close it, scaling claims down proportionally if they'd overcommit
+    /// the portfolio and dropping any adjustment under `min_trade_volume`.
+    pub fn rebalance(
+        &self,
+        portfolio: &Portfolio,
+        broker: &mut Broker,
+        targets: &[RebalanceTarget],
+        timestamp: f64,
+    ) -> Vec<Order> {
+        let total_equity = portfolio.compute_snapshot(timestamp).total_equity;
+
+        // Bottom-up pass: the most any single holding can claim is capped
+        // by its own weight of total equity (and can't go negative).
+        let max_values: Vec<Fixed> = targets
+            .iter()
+            .map(|target| (total_equity * target.weight).clamp(Fixed::ZERO, total_equity.max(Fixed::ZERO)))
+            .collect();
+
+        // Top-down pass: if the raw claims would overcommit the portfolio,
+        // scale every claim down proportionally so they sum to total_equity.
+        let requested_total: Fixed = max_values.iter().copied().sum();
+        let scale = if requested_total > total_equity && !requested_total.is_zero() {
+            total_equity / requested_total
+        } else {
+            Fixed::ONE
+        };
+
+        let mut orders = Vec::new();
+        for (target, &max_value) in targets.iter().zip(max_values.iter()) {
+            let last_price = portfolio
+                .last_prices
+                .get(&target.market_id)
+                .and_then(|prices| prices.get(&target.side.index()))
+                .copied()
+                .unwrap_or(Fixed::ZERO);
+            if last_price.is_zero() {
+                continue;
+            }
+
+            let current_qty = portfolio
+                .positions
+                .get(&target.market_id)
+                .map_or(Fixed::ZERO, |pos| pos.quantity(target.side));
+            let current_value = current_qty * last_price;
+            let target_value = max_value * scale;
+            let delta_value = target_value - current_value;
+
+            // Final pass: skip adjustments too small to bother trading.
+            if delta_value.abs() < self.min_trade_volume {
+                continue;
+            }
+
+            let delta_qty = delta_value.abs() / last_price;
+            let action = if delta_value > Fixed::ZERO {
+                OrderAction::Buy
+            } else {
+                OrderAction::Sell
+            };
+            // Rejected (not pushed through) if it would breach account
+            // health — see `Portfolio::place_order_if_healthy`.
+            if let Some(order) = portfolio.place_order_if_healthy(
+                broker,
+                &target.market_id,
+                action,
+                target.side,
+                last_price,
+                delta_qty,
+                timestamp,
+                TimeInForce::Gtc,
+                None,
+            ) {
+                orders.push(order);
+            }
+        }
+        orders
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebalance_buys_toward_target_weights_from_a_flat_portfolio() {
+        let mut portfolio = Portfolio::new(Fixed::from_f64(1000.0), Fixed::ZERO, Fixed::ZERO, 0);
+        portfolio.update_price("m", Side::YES, Fixed::from_f64(0.50));
+
+        let rebalancer = Rebalancer::new(Fixed::from_f64(1.0));
+        let targets = vec![RebalanceTarget {
+            market_id: "m".to_string(),
+            side: Side::YES,
+            weight: Fixed::from_f64(0.50),
+        }];
+        let mut broker = Broker::new(Fixed::ZERO, Fixed::ZERO, false, Fixed::from_f64(0.1));
+        let orders = rebalancer.rebalance(&portfolio, &mut broker, &targets, 0.0);
+
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].action, OrderAction::Buy);
+        assert_eq!(orders[0].quantity, 1000.0);
+    }
+
+    #[test]
+    fn rebalance_skips_deltas_below_min_trade_volume() {
+        let mut portfolio = Portfolio::new(Fixed::from_f64(1000.0), Fixed::ZERO, Fixed::ZERO, 0);
+        portfolio.update_price("m", Side::YES, Fixed::from_f64(0.50));
+
+        let rebalancer = Rebalancer::new(Fixed::from_f64(1000.0));
+        let targets = vec![RebalanceTarget {
+            market_id: "m".to_string(),
+            side: Side::YES,
+            weight: Fixed::from_f64(0.001),
+        }];
+        let mut broker = Broker::new(Fixed::ZERO, Fixed::ZERO, false, Fixed::from_f64(0.1));
+        let orders = rebalancer.rebalance(&portfolio, &mut broker, &targets, 0.0);
+
+        assert!(orders.is_empty());
+    }
+}